@@ -0,0 +1,97 @@
+// Extracts fenced code blocks out of a section's prose so they can be
+// compiled and run as tests, the way skeptic turns a crate's README into
+// a generated test file.
+
+use comrak::nodes::{AstNode, NodeValue};
+use comrak::{Arena, ComrakOptions};
+
+// A single fenced code block pulled out of a section's markdown.
+#[derive(Debug, Clone)]
+pub struct CodeSample {
+    pub section: usize,
+    pub lang: Option<String>,
+    pub code: String,
+    pub no_run: bool,
+    pub ignore: bool,
+    pub should_panic: bool,
+}
+
+// Walks `markdown`'s comrak AST and returns every fenced code block found
+// in it, tagging each with the section it came from.
+pub fn extract(markdown: &str, section: usize) -> Vec<CodeSample> {
+    let arena = Arena::new();
+    let root = comrak::parse_document(&arena, markdown, &ComrakOptions::default());
+    let mut samples = Vec::new();
+    collect_code_blocks(root, section, &mut samples);
+    samples
+}
+
+fn collect_code_blocks<'a>(node: &'a AstNode<'a>, section: usize, out: &mut Vec<CodeSample>) {
+    if let NodeValue::CodeBlock(block) = &node.data.borrow().value {
+        // the info string is `lang,flag,flag` e.g. `rust,no_run,should_panic`
+        let mut flags = block.info.split(',').map(str::trim);
+        let lang = flags.next().filter(|s| !s.is_empty()).map(str::to_string);
+        let mut sample = CodeSample {
+            section,
+            lang,
+            code: block.literal.clone(),
+            no_run: false,
+            ignore: false,
+            should_panic: false,
+        };
+        for flag in flags {
+            match flag {
+                "no_run" => sample.no_run = true,
+                "ignore" => sample.ignore = true,
+                "should_panic" => sample.should_panic = true,
+                _ => {}
+            }
+        }
+        out.push(sample);
+    }
+    for child in node.children() {
+        collect_code_blocks(child, section, out);
+    }
+}
+
+// Renders `samples` into a standalone Rust source file, one `#[test]` per
+// sample, honoring `no_run` (wrap in an uncalled fn), `ignore`, and
+// `should_panic` the same way skeptic's generated tests do. Samples
+// tagged with a language other than `rust` (or untagged) are skipped,
+// since there's no interpreter to run them with.
+pub fn generate_test_file(samples: &[CodeSample]) -> String {
+    let mut out = String::from("// Generated by `Docco::extract_tests` - do not edit by hand.\n\n");
+    for (i, sample) in samples.iter().enumerate() {
+        if sample.lang.as_deref() != Some("rust") {
+            continue;
+        }
+
+        if sample.ignore {
+            out.push_str("#[ignore]\n");
+        }
+        if sample.should_panic {
+            out.push_str("#[should_panic]\n");
+        }
+        out.push_str(&format!(
+            "#[test]\nfn doc_sample_section_{}_{}() {{\n",
+            sample.section, i
+        ));
+        if sample.no_run {
+            out.push_str("    #[allow(dead_code)]\n    fn run() {\n");
+            for line in sample.code.lines() {
+                out.push_str("        ");
+                out.push_str(line);
+                out.push('\n');
+            }
+            out.push_str("    }\n");
+        } else {
+            for line in sample.code.lines() {
+                out.push_str("    ");
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+        out.push_str("}\n\n");
+    }
+    out
+}
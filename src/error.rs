@@ -21,4 +21,8 @@ pub enum Error {
     UnsupportedExt(String),
     #[error("Could not find extension of source file or Unsupported source file")]
     NoExtension,
+    #[error("Unknown syntect theme: {0}")]
+    UnsupportedTheme(String),
+    #[error("Syntax highlighting failed")]
+    HighlightFailed,
 }
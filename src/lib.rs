@@ -1,7 +1,14 @@
 //! rocco is a [http://ashkenas.com/docco](http://ashkenas.com/docco) inspired literate programmming library
 //! It produces an HTML document that displays your comments intermingled with your code.
 //! All prose is passed through Markdown (using [comrak](https://crates.io/comrak)),
-//! and code is passed through prism.js syntax highlighting.
+//! and code is passed through prism.js syntax highlighting by default, or
+//! highlighted server-side with [syntect](https://crates.io/syntect) via
+//! [`Docco::with_highlighter`] for fully standalone output. Fenced code
+//! blocks found in the prose can be pulled back out with
+//! [`Docco::extract_tests`] and written to a runnable test file. For
+//! whole directories of sources, [`Project`] renders each to its own page
+//! plus a shared `index.html` and file-switcher. [`Docco::with_source_links`]
+//! adds a "view source" link to each section.
 //!
 //! Rocco has a simple API:
 //!```no_run
@@ -15,9 +22,14 @@
 //! docco.render().unwrap();
 //! ```
 
+mod doctest;
 mod error;
+mod project;
+mod toc;
 
+pub use doctest::CodeSample;
 use error::Error;
+pub use project::{FileLink, Project};
 use once_cell::sync::Lazy;
 use ramhorns::{Content, Template};
 use std::collections::HashMap;
@@ -33,6 +45,12 @@ pub struct Language {
     name: String,
     // the delimiter which denotes a comment
     comment: String,
+    // the opening delimiter of a block/multi-line comment, e.g. `/*`
+    #[serde(default)]
+    comment_start: Option<String>,
+    // the closing delimiter of a block/multi-line comment, e.g. `*/`
+    #[serde(default)]
+    comment_end: Option<String>,
 }
 
 static LANGUAGES: Lazy<HashMap<&'static str, Language>> = Lazy::new(|| {
@@ -40,23 +58,80 @@ static LANGUAGES: Lazy<HashMap<&'static str, Language>> = Lazy::new(|| {
     serde_json::from_str(lang_json).expect("Language map initialization failed")
 });
 
+static SYNTAX_SET: Lazy<syntect::parsing::SyntaxSet> =
+    Lazy::new(syntect::parsing::SyntaxSet::load_defaults_newlines);
+
+static THEME_SET: Lazy<syntect::highlighting::ThemeSet> =
+    Lazy::new(syntect::highlighting::ThemeSet::load_defaults);
+
+// Selects how code sections are turned into HTML. The default relies on
+// prism.js running client-side; `Syntect` highlights server-side so the
+// output is a self-contained document that also renders offline.
+pub enum Highlighter {
+    Syntect { theme: String },
+}
+
+// `Highlighter` is only ever read back by `parse_code`, never rendered, so
+// it uses ramhorns's default `Content`, which renders nothing.
+impl Content for Highlighter {}
+
+// Percent-encodes everything but unreserved characters, enough to put
+// arbitrary source code into the Playground's `?code=` query parameter.
+fn percent_encode(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
 #[derive(Content, Debug)]
 pub struct Section {
     num: usize,
     docs_html: String,
     code_html: String,
+    // the section's prose before markdown rendering, kept around so
+    // `Docco::extract_tests` can pull fenced code blocks back out of it
+    doc: String,
+    // the 1-based line in the source file where this section begins
+    start_line: usize,
+    // set when `Docco::with_source_links` is configured; either a
+    // Rust Playground link or a line-anchored repository link
+    source_link: Option<String>,
 }
 
 #[derive(Content)]
 pub struct Docco {
     sections: Vec<Section>,
+    // the table of contents, built from the headings in `sections`' prose
+    toc: Vec<toc::TocEntry>,
     css: String,
     html: String,
-    filename: String,
-    output: String,
+    pub(crate) filename: String,
+    pub(crate) output: String,
     extension: String,
     language: String,
     doc_symbol: String,
+    comment_start: Option<String>,
+    comment_end: Option<String>,
+    // whether a server-side highlighter is active; used by the template to
+    // skip loading prism.js when the code is already highlighted
+    highlighted: bool,
+    highlighter: Option<Highlighter>,
+    // every file in this `Project`, this one included and marked selected,
+    // rendered as a file-switcher dropdown; empty when `Docco` is used standalone
+    pub(crate) files: Vec<FileLink>,
+    // whether `files` is non-empty; gates the file-switcher dropdown so a
+    // standalone `Docco` (no `Project`) doesn't render an empty `<select>`
+    pub(crate) has_files: bool,
+    // base URL for the "view source" links rendered on each section, set
+    // via `with_source_links`; Rust sources link to the Playground instead
+    source_link_base: Option<String>,
 }
 
 impl Docco {
@@ -89,27 +164,55 @@ impl Docco {
             format!("{}.html", source)
         };
 
-        let (lang, cmnt, extn) = if let Some(ext) = source.extension().and_then(|s| s.to_str()) {
-            let lang = LANGUAGES
+        let lang = if let Some(ext) = source.extension().and_then(|s| s.to_str()) {
+            LANGUAGES
                 .get(ext)
-                .ok_or_else(|| Error::UnsupportedExt(ext.to_string()))?;
-            (&lang.name, &lang.comment, ext.to_string())
+                .ok_or_else(|| Error::UnsupportedExt(ext.to_string()))?
         } else {
             return Err(Error::InvalidSourceFile);
         };
+        let extn = source
+            .extension()
+            .and_then(|s| s.to_str())
+            .expect("checked above")
+            .to_string();
 
         Ok(Self {
             sections: vec![],
+            toc: vec![],
             filename: source_str,
             css: include_str!("assets/template.css").to_string(),
             html: include_str!("assets/template.html").to_string(),
             output,
-            language: lang.to_string(),
+            language: lang.name.clone(),
             extension: extn,
-            doc_symbol: cmnt.to_string(),
+            doc_symbol: lang.comment.clone(),
+            comment_start: lang.comment_start.clone(),
+            comment_end: lang.comment_end.clone(),
+            highlighted: false,
+            highlighter: None,
+            files: vec![],
+            has_files: false,
+            source_link_base: None,
         })
     }
 
+    // Enables server-side syntax highlighting for code sections, producing
+    // fully standalone HTML that no longer depends on prism.js.
+    pub fn with_highlighter(mut self, highlighter: Highlighter) -> Self {
+        self.highlighted = true;
+        self.highlighter = Some(highlighter);
+        self
+    }
+
+    // Renders a "view source" link on each section: a Rust Playground link
+    // with the section's code URL-encoded into `?code=` for Rust sources,
+    // or a line-anchored link into `base_url` for everything else.
+    pub fn with_source_links(mut self, base_url: impl Into<String>) -> Self {
+        self.source_link_base = Some(base_url.into());
+        self
+    }
+
     pub fn render(&self) -> Result<(), Error> {
         let template =
             Template::new(self.html.as_str()).map_err(|_| Error::InvalidTemplateSource)?;
@@ -120,39 +223,133 @@ impl Docco {
         Ok(())
     }
 
+    // Pulls every fenced code block out of the parsed sections' prose, so
+    // the examples embedded in the literate documentation can be checked.
+    pub fn extract_tests(&self) -> Vec<CodeSample> {
+        self.sections
+            .iter()
+            .flat_map(|section| doctest::extract(&section.doc, section.num))
+            .collect()
+    }
+
+    // Writes a generated `#[test]` source file for `self.extract_tests()`
+    // to `path`, mirroring how `render` writes the rendered HTML.
+    pub fn write_tests(&self, path: &std::path::Path) -> Result<(), Error> {
+        let samples = self.extract_tests();
+        std::fs::write(path, doctest::generate_test_file(&samples))?;
+        Ok(())
+    }
+
+    // whether `line` opens a line comment, e.g. `// ...` or `# ...`
+    fn is_line_comment(&self, line: &str) -> bool {
+        !self.doc_symbol.is_empty() && line.starts_with(&self.doc_symbol)
+    }
+
+    // whether `line` opens a block/multi-line comment, e.g. `/*` or `<!--`
+    fn is_block_comment_start(&self, line: &str) -> bool {
+        self.comment_start
+            .as_deref()
+            .is_some_and(|start| line.starts_with(start))
+    }
+
+    // `leading`, when given, is code trailing a block comment's closing
+    // delimiter on the same physical line (e.g. `*/ int x = 1;`) - it's
+    // treated as the first line of code, ahead of whatever `iter` yields.
     fn parse_code(
         &self,
         iter: &mut Peekable<Lines<BufReader<File>>>,
         code_buffer: &mut String,
+        raw_buffer: &mut String,
+        line_no: &mut usize,
+        leading: Option<String>,
     ) -> Result<(), Error> {
+        let mut raw_lines = vec![];
+        if let Some(line) = leading {
+            raw_lines.push(line);
+        }
         while let Some(Ok(next_line)) = iter.peek() {
             let line_trimmed = next_line.trim_start();
-            if !line_trimmed.starts_with(&self.doc_symbol) && !line_trimmed.is_empty() {
-                let next_line = next_line.replace("<", "&lt");
-                let next_line = next_line.replace(">", "&gt");
-                code_buffer.push_str(&next_line);
-                if !line_trimmed.ends_with('\n') {
-                    code_buffer.push_str("\n");
-                }
-
+            let is_comment =
+                self.is_line_comment(line_trimmed) || self.is_block_comment_start(line_trimmed);
+            if !is_comment && !line_trimmed.is_empty() {
+                raw_lines.push(next_line.clone());
                 iter.next();
+                *line_no += 1;
             } else {
-                return Ok(());
+                break;
+            }
+        }
+
+        for line in &raw_lines {
+            raw_buffer.push_str(line);
+            raw_buffer.push('\n');
+        }
+
+        match &self.highlighter {
+            Some(Highlighter::Syntect { theme }) => {
+                self.highlight_syntect(&raw_lines, theme, code_buffer)
+            }
+            None => {
+                for line in &raw_lines {
+                    code_buffer.push_str(&line.replace('<', "&lt").replace('>', "&gt"));
+                    code_buffer.push('\n');
+                }
+                Ok(())
             }
         }
+    }
+
+    // Highlights `lines` server-side with syntect, keyed by `self.extension`,
+    // and appends the resulting inline-styled `<span>` markup to `code_buffer`.
+    fn highlight_syntect(
+        &self,
+        lines: &[String],
+        theme: &str,
+        code_buffer: &mut String,
+    ) -> Result<(), Error> {
+        use syntect::easy::HighlightLines;
+        use syntect::html::{styled_line_to_highlighted_html, IncludeBackground};
+
+        let syntax = SYNTAX_SET
+            .find_syntax_by_extension(&self.extension)
+            .unwrap_or_else(|| SYNTAX_SET.find_syntax_plain_text());
+        let theme = THEME_SET
+            .themes
+            .get(theme)
+            .ok_or_else(|| Error::UnsupportedTheme(theme.to_string()))?;
+        let mut highlighter = HighlightLines::new(syntax, theme);
+
+        for line in lines {
+            let line = format!("{}\n", line);
+            let ranges = highlighter
+                .highlight_line(&line, &SYNTAX_SET)
+                .map_err(|_| Error::HighlightFailed)?;
+            let html = styled_line_to_highlighted_html(&ranges, IncludeBackground::No)
+                .map_err(|_| Error::HighlightFailed)?;
+            code_buffer.push_str(&html);
+        }
 
         Ok(())
     }
 
+    // Returns code trailing a block comment's closing delimiter on the
+    // same line, if any - the caller feeds it into `parse_code` as the
+    // first line of the section's code, since doc parsing stops there.
     fn parse_doc(
         &self,
         iter: &mut Peekable<Lines<BufReader<File>>>,
         doc_buffer: &mut String,
-    ) -> Result<(), Error> {
+        line_no: &mut usize,
+    ) -> Result<Option<String>, Error> {
         while let Some(Ok(next_line)) = iter.peek() {
-            if next_line.trim().starts_with(&self.doc_symbol) {
+            let trimmed = next_line.trim();
+            if self.is_block_comment_start(trimmed) {
+                if let Some(trailing) = self.parse_block_doc(iter, doc_buffer, line_no)? {
+                    return Ok(Some(trailing));
+                }
+            } else if self.is_line_comment(trimmed) {
                 // rust specific doc comments
-                if next_line.trim().starts_with("///") {
+                if trimmed.starts_with("///") {
                     doc_buffer.push_str(next_line.trim_start());
                     doc_buffer.push_str("\n");
                 } else {
@@ -161,43 +358,159 @@ impl Docco {
                     doc_buffer.push_str("\n");
                 }
                 iter.next();
+                *line_no += 1;
             } else {
-                return Ok(());
+                return Ok(None);
             }
         }
 
-        Ok(())
+        Ok(None)
+    }
+
+    // Renders the syntect theme's background/foreground into a CSS rule so
+    // the standalone page's colors match the highlighted code.
+    fn theme_css(&self, theme: &str) -> Result<String, Error> {
+        let theme = THEME_SET
+            .themes
+            .get(theme)
+            .ok_or_else(|| Error::UnsupportedTheme(theme.to_string()))?;
+        let settings = &theme.settings;
+        let mut css = String::new();
+        if let (Some(bg), Some(fg)) = (settings.background, settings.foreground) {
+            css.push_str(&format!(
+                "body {{ background: #{:02x}{:02x}{:02x}; color: #{:02x}{:02x}{:02x}; }}",
+                bg.r, bg.g, bg.b, fg.r, fg.g, fg.b
+            ));
+        }
+        Ok(css)
+    }
+
+    // Builds the "view source" link for a section, if `with_source_links`
+    // was configured: a Rust Playground link carrying `code` for Rust
+    // sources, or a line-anchored link into the configured repository
+    // otherwise.
+    fn source_link(&self, code: &str, start_line: usize) -> Option<String> {
+        let base_url = self.source_link_base.as_ref()?;
+        if self.extension == "rs" {
+            Some(format!(
+                "https://play.rust-lang.org/?code={}",
+                percent_encode(code)
+            ))
+        } else {
+            Some(format!("{}#L{}", base_url.trim_end_matches('/'), start_line))
+        }
+    }
+
+    // Accumulates a block comment into `doc_buffer`, line by line, until the
+    // closing delimiter is seen - analogous to tokei's windowed `in_comments`
+    // scan, except here we keep the text instead of just counting lines.
+    // Returns any code trailing the closing delimiter on that same line
+    // (e.g. `*/ int x = 1;`), so the caller can feed it back in as code
+    // instead of discarding it.
+    //
+    // The closing delimiter is searched for *before* stripping a leading `*`
+    // continuation marker, since a bare `*/` would otherwise lose its `*`
+    // and never be recognized as the close. The opening delimiter is only
+    // stripped from the very first line of the block, not every line -
+    // for languages like Python where `comment_start == comment_end`, a
+    // closing-only line would otherwise look like another opening and have
+    // its delimiter eaten before `find(end)` ever sees it.
+    fn parse_block_doc(
+        &self,
+        iter: &mut Peekable<Lines<BufReader<File>>>,
+        doc_buffer: &mut String,
+        line_no: &mut usize,
+    ) -> Result<Option<String>, Error> {
+        let start = self.comment_start.as_deref().unwrap_or_default();
+        let end = self.comment_end.as_deref().unwrap_or_default();
+        let mut first_line = true;
+        loop {
+            let line = match iter.next() {
+                Some(Ok(line)) => line,
+                _ => return Ok(None),
+            };
+            *line_no += 1;
+            let mut content = line.trim_start();
+            if first_line {
+                content = content.trim_start_matches(start);
+                first_line = false;
+            }
+            if let Some(end_pos) = content.find(end) {
+                let trailing = content[end_pos + end.len()..].trim();
+                let content = content[..end_pos].trim_start().trim_start_matches('*');
+                doc_buffer.push_str(content.trim());
+                doc_buffer.push('\n');
+                return Ok(if trailing.is_empty() {
+                    None
+                } else {
+                    Some(trailing.to_string())
+                });
+            } else {
+                let content = content.trim_start().trim_start_matches('*');
+                doc_buffer.push_str(content.trim_start());
+                doc_buffer.push('\n');
+            }
+        }
     }
 
     pub fn parse(&mut self) -> Result<(), Error> {
+        if let Some(Highlighter::Syntect { theme }) = &self.highlighter {
+            self.css.push_str(&self.theme_css(theme)?);
+        }
+
         let fs = BufReader::new(OpenOptions::new().read(true).open(&self.filename)?);
         let mut lines = fs.lines().peekable();
         let mut idx = 0;
+        let mut line_no = 0;
+        let mut id_map = toc::IdMap::new();
+        let mut toc_builder = toc::TocBuilder::new();
         while let Some(Ok(next_line)) = lines.peek() {
             if next_line.is_empty() {
                 lines.next();
+                line_no += 1;
                 continue;
             }
+            let start_line = line_no + 1;
             let mut doc = String::new();
             let mut code = String::new();
-            self.parse_doc(&mut lines, &mut doc)?;
-            self.parse_code(&mut lines, &mut code)?;
-            let docs_html = comrak::markdown_to_html(&doc, &comrak::ComrakOptions::default());
+            let mut raw_code = String::new();
+            let trailing = self.parse_doc(&mut lines, &mut doc, &mut line_no)?;
+            self.parse_code(&mut lines, &mut code, &mut raw_code, &mut line_no, trailing)?;
+            let mut docs_html = comrak::markdown_to_html(&doc, &comrak::ComrakOptions::default());
+
+            let headings = toc::extract_headings(&doc);
+            if !headings.is_empty() {
+                let mut ids = Vec::with_capacity(headings.len());
+                for (level, name) in headings {
+                    let id = id_map.derive_id(&name);
+                    toc_builder.push(level, id.clone(), name);
+                    ids.push(id);
+                }
+                docs_html = toc::inject_heading_ids(&docs_html, &ids);
+            }
+
+            let source_link = self.source_link(&raw_code, start_line);
+
             let section = Section {
                 num: idx,
                 docs_html,
                 code_html: code,
+                doc,
+                start_line,
+                source_link,
             };
             self.sections.push(section);
             idx += 1;
         }
+        self.toc = toc_builder.into_toc();
         Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Docco;
+    use super::{Docco, Highlighter};
+    use crate::Project;
     use std::path::{Path, PathBuf};
 
     #[test]
@@ -263,4 +576,200 @@ mod tests {
         assert!(Path::new("source.html").exists());
         std::fs::remove_file("source.html").unwrap();
     }
+
+    // Regression test for a bug where detecting the closing `*/` after
+    // stripping the `*` continuation marker destroyed the delimiter,
+    // so the loop never found it and swallowed the rest of the file
+    // (the `sub` function) into the doc buffer.
+    #[test]
+    fn block_comment_c_does_not_swallow_code_to_eof() {
+        let mut docco = Docco::new(PathBuf::from("tests/samples/block_comments.c"), None).unwrap();
+        docco.parse().unwrap();
+        docco.render().unwrap();
+
+        assert_eq!(docco.sections.len(), 4);
+        assert!(docco.sections[2].code_html.contains("int sub"));
+        assert!(!docco.sections[2].docs_html.contains("int sub"));
+        std::fs::remove_file(docco.output).unwrap();
+    }
+
+    // Regression test for a bug where code trailing a block comment's
+    // closing delimiter on the same line (`*/ int x = 1;`) was truncated
+    // away entirely instead of being fed back in as code.
+    #[test]
+    fn block_comment_c_keeps_code_trailing_inline_closer() {
+        let mut docco = Docco::new(PathBuf::from("tests/samples/block_comments.c"), None).unwrap();
+        docco.parse().unwrap();
+        docco.render().unwrap();
+
+        let oneliner = docco
+            .sections
+            .iter()
+            .find(|section| section.docs_html.contains("oneliner"))
+            .expect("the oneliner comment should produce a section");
+        assert!(oneliner.code_html.contains("int x = 1;"));
+        std::fs::remove_file(docco.output).unwrap();
+    }
+
+    #[test]
+    fn block_comment_css_does_not_swallow_code_to_eof() {
+        let mut docco =
+            Docco::new(PathBuf::from("tests/samples/block_comments.css"), None).unwrap();
+        docco.parse().unwrap();
+        docco.render().unwrap();
+
+        assert_eq!(docco.sections.len(), 2);
+        assert!(docco.sections[1].code_html.contains("color: blue"));
+        std::fs::remove_file(docco.output).unwrap();
+    }
+
+    // Regression test for `comment_start == comment_end` (e.g. Python's
+    // `"""`): stripping the opening delimiter from every line, not just
+    // the first, destroyed a closing-only line before it could be found.
+    #[test]
+    fn block_comment_python_does_not_swallow_code_to_eof() {
+        let mut docco =
+            Docco::new(PathBuf::from("tests/samples/block_comments.py"), None).unwrap();
+        docco.parse().unwrap();
+        docco.render().unwrap();
+
+        assert_eq!(docco.sections.len(), 2);
+        assert!(docco.sections[1].code_html.contains("def sub"));
+        std::fs::remove_file(docco.output).unwrap();
+    }
+
+    #[test]
+    fn toc_is_flattened_to_two_levels() {
+        let mut docco = Docco::new(PathBuf::from("tests/samples/toc_headings.rs"), None).unwrap();
+        docco.parse().unwrap();
+        docco.render().unwrap();
+
+        assert_eq!(docco.toc.len(), 1);
+        assert_eq!(docco.toc[0].name, "Top Heading");
+        // the `h3` "Third Level" nested two levels deep would be dropped
+        // by a template that only renders `{{#toc}}`/`{{#children}}` -
+        // it must be flattened alongside "Second Level" instead.
+        assert_eq!(docco.toc[0].children.len(), 2);
+        assert_eq!(docco.toc[0].children[0].name, "Second Level");
+        assert_eq!(docco.toc[0].children[1].name, "Third Level");
+        std::fs::remove_file(docco.output).unwrap();
+    }
+
+    #[test]
+    fn syntect_highlighter_renders_spans_instead_of_prism_classes() {
+        let mut docco = Docco::new(PathBuf::from("tests/samples/source.rs"), None)
+            .unwrap()
+            .with_highlighter(Highlighter::Syntect {
+                theme: "InspiredGitHub".to_string(),
+            });
+        docco.parse().unwrap();
+        docco.render().unwrap();
+
+        assert!(docco.highlighted);
+        let section = docco
+            .sections
+            .iter()
+            .find(|section| !section.code_html.is_empty())
+            .expect("at least one section should have code");
+        assert!(section.code_html.contains("<span style="));
+        std::fs::remove_file(docco.output).unwrap();
+    }
+
+    // Regression test for a bug where the Playground link was built from
+    // the post-processed `code_html` (HTML-escaped or syntax-highlighted),
+    // rather than the raw source - producing broken `?code=` URLs.
+    #[test]
+    fn source_link_encodes_raw_code_not_escaped_html() {
+        let mut docco = Docco::new(PathBuf::from("tests/samples/source.rs"), None)
+            .unwrap()
+            .with_source_links("https://github.com/example/example");
+        docco.parse().unwrap();
+        docco.render().unwrap();
+
+        // find a section whose displayed code was HTML-escaped (i.e. its
+        // raw source contains a `<`), so the link can prove it was built
+        // from the raw `<`, not the escaped `&lt` entity.
+        let section = docco
+            .sections
+            .iter()
+            .find(|section| section.code_html.contains("&lt"))
+            .expect("at least one section's code should contain an escaped `<`");
+        let link = section.source_link.as_ref().expect("source link should be set");
+        assert!(link.starts_with("https://play.rust-lang.org/?code="));
+        assert!(link.contains("%3C"));
+        assert!(!link.contains("%26lt"));
+        std::fs::remove_file(docco.output).unwrap();
+    }
+
+    #[test]
+    fn project_renders_file_switcher_and_index() {
+        let output_dir = PathBuf::from("tests/project_output");
+        std::fs::create_dir_all(&output_dir).unwrap();
+        let sources = vec![
+            PathBuf::from("tests/samples/block_comments.c"),
+            PathBuf::from("tests/samples/toc_headings.rs"),
+        ];
+        let mut project = Project::from_paths(sources, output_dir.clone()).unwrap();
+        project.render().unwrap();
+
+        assert!(output_dir.join("index.html").exists());
+        assert!(output_dir.join("block_comments.html").exists());
+        let page = std::fs::read_to_string(output_dir.join("block_comments.html")).unwrap();
+        assert!(page.contains("id=\"file-switcher\""));
+        assert!(page.contains("value=\"block_comments.html\" selected"));
+
+        std::fs::remove_dir_all(output_dir).unwrap();
+    }
+
+    #[test]
+    fn extract_tests_tags_fenced_blocks_by_info_string() {
+        let mut docco = Docco::new(PathBuf::from("tests/samples/doc_tests.rs"), None).unwrap();
+        docco.parse().unwrap();
+        docco.render().unwrap();
+
+        let samples = docco.extract_tests();
+        assert_eq!(samples.len(), 5);
+
+        assert_eq!(samples[0].lang, None);
+        assert!(!samples[0].no_run && !samples[0].ignore && !samples[0].should_panic);
+
+        assert_eq!(samples[1].lang.as_deref(), Some("rust"));
+        assert!(samples[1].no_run);
+
+        assert_eq!(samples[2].lang.as_deref(), Some("rust"));
+        assert!(samples[2].ignore);
+
+        assert_eq!(samples[3].lang.as_deref(), Some("rust"));
+        assert!(samples[3].should_panic);
+
+        assert_eq!(samples[4].lang.as_deref(), Some("text"));
+
+        std::fs::remove_file(docco.output).unwrap();
+    }
+
+    // Untagged blocks default to prose, not Rust - a fenced shell/console
+    // transcript with no language tag must not be compiled as a test.
+    #[test]
+    fn write_tests_skips_untagged_and_non_rust_blocks() {
+        let mut docco = Docco::new(PathBuf::from("tests/samples/doc_tests.rs"), None).unwrap();
+        docco.parse().unwrap();
+        docco.render().unwrap();
+
+        let path = std::path::Path::new("tests/doc_tests_generated.rs");
+        docco.write_tests(path).unwrap();
+        let generated = std::fs::read_to_string(path).unwrap();
+
+        assert!(!generated.contains("not rust at all"));
+        assert!(!generated.contains("assert_eq!(1 + 2, 3);"));
+
+        assert!(generated.contains("loop {}"));
+        assert!(generated.contains("does_not_compile();"));
+        assert!(generated.contains("panic!(\"boom\");"));
+        assert!(generated.contains("#[ignore]"));
+        assert!(generated.contains("#[should_panic]"));
+        assert!(generated.contains("#[allow(dead_code)]"));
+
+        std::fs::remove_file(path).unwrap();
+        std::fs::remove_file(docco.output).unwrap();
+    }
 }
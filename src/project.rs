@@ -0,0 +1,95 @@
+// Renders a whole directory of literate sources to a documentation site,
+// the way the original docco renders every file in a project with a
+// jump-to-file menu, rather than rocco's usual single `Docco` per source.
+
+use crate::{Docco, Error};
+use ramhorns::Content;
+use std::path::PathBuf;
+
+// A single entry in the shared file-switcher dropdown and the index page.
+#[derive(Content, Debug, Clone)]
+pub struct FileLink {
+    pub name: String,
+    pub href: String,
+    // set on the entry matching the page the dropdown is rendered on, so
+    // the `<select>` opens showing the file currently being viewed
+    pub selected: bool,
+}
+
+// The file-switcher `href` for `doc`: its output path relative to the
+// shared `output_dir`, i.e. just the HTML file's own name.
+fn file_href(doc: &Docco) -> String {
+    std::path::Path::new(&doc.output)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(&doc.output)
+        .to_string()
+}
+
+// A multi-file literate project: one `Docco` per source file, all sharing
+// an output directory, a file-switcher, and an `index.html`.
+pub struct Project {
+    output_dir: PathBuf,
+    docs: Vec<Docco>,
+}
+
+impl Project {
+    // Sets up a `Docco` for every source in `sources`, each to be rendered
+    // into its own HTML file inside `output_dir`; parsing happens in `render`.
+    pub fn from_paths(sources: Vec<PathBuf>, output_dir: PathBuf) -> Result<Self, Error> {
+        let docs = sources
+            .into_iter()
+            .map(|source| Docco::new(source, Some(output_dir.clone())))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { output_dir, docs })
+    }
+
+    // Parses and renders every source, injecting a shared file-switcher
+    // into each page, then emits an `index.html` listing all of them.
+    pub fn render(&mut self) -> Result<(), Error> {
+        let files: Vec<FileLink> = self
+            .docs
+            .iter()
+            .map(|doc| FileLink {
+                name: doc.filename.clone(),
+                href: file_href(doc),
+                selected: false,
+            })
+            .collect();
+
+        for doc in &mut self.docs {
+            let own_href = file_href(doc);
+            doc.files = files
+                .iter()
+                .cloned()
+                .map(|mut link| {
+                    link.selected = link.href == own_href;
+                    link
+                })
+                .collect();
+            doc.has_files = true;
+            doc.parse()?;
+            doc.render()?;
+        }
+
+        self.render_index(&files)
+    }
+
+    fn render_index(&self, files: &[FileLink]) -> Result<(), Error> {
+        let mut links = String::new();
+        for file in files {
+            links.push_str(&format!(
+                "<li><a href=\"{}\">{}</a></li>\n",
+                file.href, file.name
+            ));
+        }
+        let html = format!(
+            "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>Index</title></head>\n\
+             <body>\n<ul>\n{}</ul>\n</body>\n</html>\n",
+            links
+        );
+        std::fs::create_dir_all(&self.output_dir)?;
+        std::fs::write(self.output_dir.join("index.html"), html)?;
+        Ok(())
+    }
+}
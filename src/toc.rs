@@ -0,0 +1,198 @@
+// A navigable table of contents, built while `Docco::parse` walks each
+// section's markdown - modeled on rustdoc's `TocBuilder`/`IdMap`.
+
+use comrak::nodes::{AstNode, NodeValue};
+use comrak::{Arena, ComrakOptions};
+use ramhorns::Content;
+use std::collections::HashMap;
+
+// A single heading in the generated table of contents, nested under its
+// top-level heading; headings below the second level are flattened into
+// the second level (see `flatten_to_two_levels`).
+#[derive(Content, Debug, Clone)]
+pub struct TocEntry {
+    pub level: u8,
+    pub id: String,
+    pub name: String,
+    pub children: Vec<TocEntry>,
+}
+
+// Assigns unique, stable slug ids to heading text. Mirrors rustdoc's
+// `IdMap::derive`: a fresh slug is returned as-is, and a slug seen before
+// gets a `-1`, `-2`, ... suffix so headings never collide.
+#[derive(Default)]
+pub struct IdMap {
+    seen: HashMap<String, usize>,
+}
+
+impl IdMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn derive_id(&mut self, text: &str) -> String {
+        let slug = slugify(text);
+        let id = match self.seen.get_mut(&slug) {
+            None => slug.clone(),
+            Some(count) => {
+                let id = format!("{}-{}", slug, *count);
+                *count += 1;
+                id
+            }
+        };
+        self.seen.insert(id.clone(), 1);
+        id
+    }
+}
+
+// Lowercases `text` and collapses runs of non-alphanumeric characters into
+// a single `-`, the same rule rustdoc's `derive_id` uses for heading slugs.
+fn slugify(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+    let mut last_was_dash = true; // avoid a leading dash
+    for ch in text.trim().chars() {
+        if ch.is_ascii_alphanumeric() {
+            slug.push(ch.to_ascii_lowercase());
+            last_was_dash = false;
+        } else if !last_was_dash {
+            slug.push('-');
+            last_was_dash = true;
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+// Builds a nested `Vec<TocEntry>` from a flat stream of `(level, id, name)`
+// headings, folding deeper headings into the `children` of the last
+// heading at a shallower level - the same stack-based approach rustdoc's
+// `TocBuilder` uses, minus its section-numbering.
+#[derive(Default)]
+pub struct TocBuilder {
+    top_level: Vec<TocEntry>,
+    chain: Vec<TocEntry>,
+}
+
+impl TocBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, level: u8, id: String, name: String) {
+        while let Some(last) = self.chain.last() {
+            if last.level >= level {
+                let entry = self.chain.pop().expect("just checked");
+                self.attach(entry);
+            } else {
+                break;
+            }
+        }
+        self.chain.push(TocEntry {
+            level,
+            id,
+            name,
+            children: vec![],
+        });
+    }
+
+    fn attach(&mut self, entry: TocEntry) {
+        match self.chain.last_mut() {
+            Some(parent) => parent.children.push(entry),
+            None => self.top_level.push(entry),
+        }
+    }
+
+    pub fn into_toc(mut self) -> Vec<TocEntry> {
+        while let Some(entry) = self.chain.pop() {
+            self.attach(entry);
+        }
+        flatten_to_two_levels(self.top_level)
+    }
+}
+
+// The template only renders two levels (`{{#toc}}` then `{{#children}}`),
+// so anything nested deeper than that would silently vanish from the
+// sidebar. Flatten every level past the second into the second level's
+// `children`, in document order, rather than dropping it.
+fn flatten_to_two_levels(top_level: Vec<TocEntry>) -> Vec<TocEntry> {
+    top_level
+        .into_iter()
+        .map(|mut entry| {
+            let mut children = Vec::new();
+            for child in std::mem::take(&mut entry.children) {
+                flatten_into(child, &mut children);
+            }
+            entry.children = children;
+            entry
+        })
+        .collect()
+}
+
+// Pushes `entry` onto `out`, then recursively does the same for its own
+// children (cleared first), so a 3+ level chain lands as a flat run of
+// second-level siblings instead of being discarded.
+fn flatten_into(mut entry: TocEntry, out: &mut Vec<TocEntry>) {
+    let descendants = std::mem::take(&mut entry.children);
+    out.push(entry);
+    for descendant in descendants {
+        flatten_into(descendant, out);
+    }
+}
+
+// Walks `markdown`'s comrak AST and returns the `(level, text)` of every
+// heading, in document order, so the caller can assign ids before the
+// matching HTML is generated.
+pub fn extract_headings(markdown: &str) -> Vec<(u8, String)> {
+    let arena = Arena::new();
+    let root = comrak::parse_document(&arena, markdown, &ComrakOptions::default());
+    let mut headings = Vec::new();
+    collect_headings(root, &mut headings);
+    headings
+}
+
+fn collect_headings<'a>(node: &'a AstNode<'a>, out: &mut Vec<(u8, String)>) {
+    if let NodeValue::Heading(heading) = &node.data.borrow().value {
+        let mut text = String::new();
+        collect_text(node, &mut text);
+        out.push((heading.level, text.trim().to_string()));
+    }
+    for child in node.children() {
+        collect_headings(child, out);
+    }
+}
+
+fn collect_text<'a>(node: &'a AstNode<'a>, out: &mut String) {
+    for child in node.children() {
+        if let NodeValue::Text(text) = &child.data.borrow().value {
+            out.push_str(text);
+        }
+        collect_text(child, out);
+    }
+}
+
+// Rewrites the plain `<h1>`..`<h6>` tags comrak emits into `<h{level}
+// id="...">`, consuming `ids` in the document order `extract_headings`
+// returned them in.
+pub fn inject_heading_ids(html: &str, ids: &[String]) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    let mut ids = ids.iter();
+    while let Some((start, tag_len, level)) = find_heading_tag(rest) {
+        out.push_str(&rest[..start]);
+        match ids.next() {
+            Some(id) => out.push_str(&format!("<h{} id=\"{}\">", level, id)),
+            None => out.push_str(&rest[start..start + tag_len]),
+        }
+        rest = &rest[start + tag_len..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn find_heading_tag(s: &str) -> Option<(usize, usize, u8)> {
+    (1..=6u8)
+        .filter_map(|level| {
+            let needle = format!("<h{}>", level);
+            s.find(&needle).map(|pos| (pos, needle.len(), level))
+        })
+        .min_by_key(|(pos, _, _)| *pos)
+}
@@ -0,0 +1,24 @@
+// Adds two numbers.
+//
+// ```
+// assert_eq!(1 + 2, 3);
+// ```
+//
+// ```rust,no_run
+// loop {}
+// ```
+//
+// ```rust,ignore
+// does_not_compile();
+// ```
+//
+// ```rust,should_panic
+// panic!("boom");
+// ```
+//
+// ```text
+// not rust at all
+// ```
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
@@ -0,0 +1,14 @@
+// # Top Heading
+//
+// Intro prose under the top heading.
+fn intro() {}
+
+// ## Second Level
+//
+// Prose under the second-level heading.
+fn second() {}
+
+// ### Third Level
+//
+// Prose nested three levels deep.
+fn third() {}